@@ -61,6 +61,7 @@ pub(crate) fn queue_outline_stencil_mesh(
     for (view, mut stencil_phase, view_mask) in views.iter_mut() {
         let rangefinder = view.rangefinder3d();
         let view_mask = view_mask.copied().unwrap_or_default();
+
         for (entity, mesh_handle, stencil_uniform, stencil_flags, outline_mask) in
             material_meshes.iter()
         {
@@ -70,23 +71,30 @@ pub(crate) fn queue_outline_stencil_mesh(
             if stencil_flags.depth_mode == DepthMode::Invalid {
                 continue; // DepthMode not propagated
             }
-            if let Some(mesh) = render_meshes.get(mesh_handle) {
-                let key = base_key
-                    .with_primitive_topology(mesh.primitive_topology)
-                    .with_depth_mode(stencil_flags.depth_mode)
-                    .with_offset_zero(stencil_uniform.offset == 0.0);
-                let pipeline = pipelines
-                    .specialize(&pipeline_cache, &stencil_pipeline, key, &mesh.layout)
-                    .unwrap();
-                let distance =
-                    rangefinder.distance(&Mat4::from_translation(stencil_uniform.origin));
-                stencil_phase.add(StencilOutline {
-                    entity,
-                    pipeline,
-                    draw_function: draw_stencil,
-                    distance,
-                });
-            }
+            let Some(mesh) = render_meshes.get(mesh_handle) else {
+                continue;
+            };
+            let key = base_key
+                .with_primitive_topology(mesh.primitive_topology)
+                .with_depth_mode(stencil_flags.depth_mode)
+                .with_offset_zero(stencil_uniform.offset == 0.0);
+            let Ok(pipeline) =
+                pipelines.specialize(&pipeline_cache, &stencil_pipeline, key, &mesh.layout)
+            else {
+                continue;
+            };
+            let distance = rangefinder.distance(&Mat4::from_translation(stencil_uniform.origin));
+            // One phase item per entity: `DrawMesh` binds the mesh's own
+            // transform via `SetMeshBindGroup`, which only produces the
+            // right result when each draw call covers exactly one entity.
+            stencil_phase.add(StencilOutline {
+                entity,
+                pipeline,
+                draw_function: draw_stencil,
+                distance,
+                batch_range: 0..1,
+                dynamic_offset: None,
+            });
         }
     }
 }
@@ -141,6 +149,7 @@ pub(crate) fn queue_outline_volume_mesh(
     for (view, mut opaque_phase, mut transparent_phase, view_mask) in views.iter_mut() {
         let view_mask = view_mask.copied().unwrap_or_default();
         let rangefinder = view.rangefinder3d();
+
         for (entity, mesh_handle, volume_uniform, volume_flags, fragment_uniform, outline_mask) in
             material_meshes.iter()
         {
@@ -150,8 +159,33 @@ pub(crate) fn queue_outline_volume_mesh(
             if volume_flags.depth_mode == DepthMode::Invalid {
                 continue; // DepthMode not propagated
             }
-            if let Some(mesh) = render_meshes.get(mesh_handle) {
-                let transparent = fragment_uniform.colour[3] < 1.0;
+            let Some(mesh) = render_meshes.get(mesh_handle) else {
+                continue;
+            };
+            let distance = rangefinder.distance(&Mat4::from_translation(volume_uniform.origin));
+
+            // An outline with an occluded style renders twice: once for the
+            // fragments that pass the normal depth test (visible portion),
+            // and once more for the fragments behind other geometry, using
+            // the inverted depth comparison. The occluded colour is read here
+            // only for its alpha, to pick that sub-pass's phase.
+            let sub_passes: &[bool] = if volume_flags.occluded_style {
+                &[false, true]
+            } else {
+                &[false]
+            };
+            for &occluded in sub_passes {
+                let colour = if occluded {
+                    fragment_uniform.occluded_colour
+                } else {
+                    fragment_uniform.colour
+                };
+                // Each sub-pass picks its phase from its own colour's alpha:
+                // an opaque visible pass can still have a translucent
+                // occluded tint (the common "dim see-through silhouette"
+                // look), and that occluded sub-pass needs alpha blending and
+                // back-to-front sorting even though the visible one doesn't.
+                let transparent = colour[3] < 1.0;
                 let key = base_key
                     .with_primitive_topology(mesh.primitive_topology)
                     .with_pass_type(if transparent {
@@ -161,17 +195,26 @@ pub(crate) fn queue_outline_volume_mesh(
                     })
                     .with_depth_mode(volume_flags.depth_mode)
                     .with_offset_zero(volume_uniform.offset == 0.0)
-                    .with_hdr_format(view.hdr);
-                let pipeline = pipelines
-                    .specialize(&pipeline_cache, &outline_pipeline, key, &mesh.layout)
-                    .unwrap();
-                let distance = rangefinder.distance(&Mat4::from_translation(volume_uniform.origin));
+                    .with_hdr_format(view.hdr)
+                    .with_occluded_style(occluded);
+                let Ok(pipeline) =
+                    pipelines.specialize(&pipeline_cache, &outline_pipeline, key, &mesh.layout)
+                else {
+                    continue;
+                };
+                // One phase item per entity per sub-pass: `DrawMesh` binds
+                // the mesh's own transform via `SetMeshBindGroup`, which only
+                // produces the right result when each draw call covers
+                // exactly one entity.
                 if transparent {
+                    // Order-dependent: sorted by distance, never batched.
                     transparent_phase.add(TransparentOutline {
                         entity,
                         pipeline,
                         draw_function: draw_transparent_outline,
                         distance,
+                        batch_range: 0..1,
+                        dynamic_offset: None,
                     });
                 } else {
                     opaque_phase.add(OpaqueOutline {
@@ -179,6 +222,8 @@ pub(crate) fn queue_outline_volume_mesh(
                         pipeline,
                         draw_function: draw_opaque_outline,
                         distance,
+                        batch_range: 0..1,
+                        dynamic_offset: None,
                     });
                 }
             }
@@ -0,0 +1,456 @@
+use bevy::pbr::{DrawMesh, SetMeshBindGroup, SetMeshViewBindGroup};
+use bevy::prelude::*;
+use bevy::render::render_asset::RenderAssets;
+use bevy::render::render_phase::{DrawFunctions, RenderPhase, SetItemPipeline};
+use bevy::render::render_resource::{PipelineCache, SpecializedMeshPipelines};
+use bevy::render::renderer::RenderAdapterInfo;
+use bevy::render::view::{ExtractedView, RenderLayers};
+use wgpu_types::Backend;
+
+use bevy::asset::load_internal_asset;
+use bevy::render::render_resource::{
+    BindGroupLayout, CachedComputePipelineId, CachedRenderPipelineId, ComputePipelineDescriptor,
+    DynamicUniformBuffer, Extent3d, RenderPipelineDescriptor, Shader, ShaderType, TextureFormat,
+    TextureUsages, TextureView,
+};
+use bevy::render::renderer::{RenderDevice, RenderQueue};
+use bevy::render::texture::TextureCache;
+
+use crate::node::JfaSeedOutline;
+use crate::pipeline::{OutlinePipeline, PassType, PipelineKey};
+use crate::uniforms::SetOutlineStencilBindGroup;
+use crate::view_uniforms::SetOutlineViewBindGroup;
+use crate::OutlineRenderLayers;
+
+pub(crate) const JFA_JUMP_FLOOD_SHADER_HANDLE: Handle<Shader> =
+    Handle::weak_from_u128(0xa6a4_0c6a_a8e2_4e5f_9c9e_2f0e_5a1d_3b11);
+pub(crate) const JFA_RESOLVE_SHADER_HANDLE: Handle<Shader> =
+    Handle::weak_from_u128(0xb7b5_1d7b_b9f3_5f60_ad0f_301f_6b2e_4c22);
+
+pub(crate) fn build(app: &mut App) {
+    load_internal_asset!(
+        app,
+        JFA_JUMP_FLOOD_SHADER_HANDLE,
+        "shaders/jfa_jump_flood.wgsl",
+        Shader::from_wgsl
+    );
+    load_internal_asset!(
+        app,
+        JFA_RESOLVE_SHADER_HANDLE,
+        "shaders/jfa_resolve.wgsl",
+        Shader::from_wgsl
+    );
+}
+
+/// Number of ping-pong jump-flood passes needed to cover a texture of the
+/// given pixel dimensions: `ceil(log2(max(width, height)))`, and at least 1.
+pub(crate) fn jump_flood_pass_count(size: Extent3d) -> u32 {
+    let max_dim = size.width.max(size.height).max(1);
+    (u32::BITS - (max_dim - 1).leading_zeros()).max(1)
+}
+
+/// The jump step used by the first pass: the smallest power of two that is
+/// at least as large as the requested outline width, in pixels.
+pub(crate) fn initial_jump_step(width: f32) -> u32 {
+    (width.max(1.0).ceil() as u32).next_power_of_two()
+}
+
+/// Per-pass jump step, uploaded to [`JfaStepBuffer`] by [`prepare_jfa_steps`]
+/// and bound at a dynamic offset by [`crate::node::JumpFloodNode`] so every
+/// pass of every view's ping-pong loop can share one compute pipeline.
+#[derive(Clone, Copy, ShaderType)]
+pub(crate) struct JfaStepUniform {
+    pub(crate) step: u32,
+}
+
+/// One [`JfaStepUniform`] per jump-flood pass, for every view, packed into a
+/// single dynamic uniform buffer and re-filled from scratch each frame by
+/// [`prepare_jfa_steps`].
+#[derive(Resource, Default)]
+pub(crate) struct JfaStepBuffer {
+    pub(crate) buffer: DynamicUniformBuffer<JfaStepUniform>,
+}
+
+/// The dynamic-uniform-buffer offset for each jump-flood pass of one view, in
+/// pass order, written alongside [`JfaTextures`].
+#[derive(Component, Default)]
+pub(crate) struct JfaStepOffsets(pub(crate) Vec<u32>);
+
+/// Fills [`JfaStepBuffer`] with this frame's jump steps for every view that
+/// has [`JfaTextures`], halving the step each pass down to a minimum of 1.
+pub(crate) fn prepare_jfa_steps(
+    mut commands: Commands,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    mut step_buffer: ResMut<JfaStepBuffer>,
+    views: Query<(Entity, &JfaTextures)>,
+) {
+    step_buffer.buffer.clear();
+    for (view_entity, jfa_textures) in views.iter() {
+        let mut step = jfa_textures.initial_step();
+        let offsets = (0..jfa_textures.pass_count())
+            .map(|_| {
+                let offset = step_buffer.buffer.push(&JfaStepUniform { step });
+                step = (step / 2).max(1);
+                offset
+            })
+            .collect();
+        commands.entity(view_entity).insert(JfaStepOffsets(offsets));
+    }
+    step_buffer
+        .buffer
+        .write_buffer(&render_device, &render_queue);
+}
+
+/// Uniform consumed by the resolve fragment shader: the outline colour and
+/// the width used for the final distance test.
+#[derive(Clone, Copy, ShaderType)]
+pub(crate) struct JfaResolveUniform {
+    pub(crate) colour: Vec4,
+    pub(crate) width: f32,
+}
+
+#[derive(Resource)]
+pub(crate) struct JfaPipeline {
+    pub(crate) jump_flood_layout: BindGroupLayout,
+    pub(crate) jump_flood_pipeline: CachedComputePipelineId,
+    pub(crate) resolve_layout: BindGroupLayout,
+    pub(crate) resolve_pipeline: CachedRenderPipelineId,
+}
+
+impl FromWorld for JfaPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        let jump_flood_layout = render_device.create_bind_group_layout(
+            "outline_jfa_jump_flood_layout",
+            &bevy::render::render_resource::BindGroupLayoutEntries::sequential(
+                bevy::render::render_resource::ShaderStages::COMPUTE,
+                (
+                    bevy::render::render_resource::binding_types::texture_2d(
+                        bevy::render::render_resource::TextureSampleType::Uint,
+                    ),
+                    bevy::render::render_resource::binding_types::texture_storage_2d(
+                        TextureFormat::Rg16Uint,
+                        bevy::render::render_resource::StorageTextureAccess::WriteOnly,
+                    ),
+                    bevy::render::render_resource::binding_types::uniform_buffer::<JfaStepUniform>(
+                        true,
+                    ),
+                ),
+            ),
+        );
+        let resolve_layout = render_device.create_bind_group_layout(
+            "outline_jfa_resolve_layout",
+            &bevy::render::render_resource::BindGroupLayoutEntries::sequential(
+                bevy::render::render_resource::ShaderStages::FRAGMENT,
+                (
+                    bevy::render::render_resource::binding_types::texture_2d(
+                        bevy::render::render_resource::TextureSampleType::Uint,
+                    ),
+                    bevy::render::render_resource::binding_types::uniform_buffer::<
+                        JfaResolveUniform,
+                    >(false),
+                ),
+            ),
+        );
+
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let jump_flood_pipeline =
+            pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
+                label: Some("outline_jfa_jump_flood_pipeline".into()),
+                layout: vec![jump_flood_layout.clone()],
+                push_constant_ranges: Vec::new(),
+                shader: JFA_JUMP_FLOOD_SHADER_HANDLE,
+                shader_defs: Vec::new(),
+                entry_point: "jump_flood".into(),
+            });
+        let resolve_pipeline = pipeline_cache.queue_render_pipeline(RenderPipelineDescriptor {
+            label: Some("outline_jfa_resolve_pipeline".into()),
+            layout: vec![resolve_layout.clone()],
+            push_constant_ranges: Vec::new(),
+            vertex: bevy::render::render_resource::VertexState {
+                shader: JFA_RESOLVE_SHADER_HANDLE,
+                shader_defs: Vec::new(),
+                entry_point: "fullscreen_vertex_shader".into(),
+                buffers: Vec::new(),
+            },
+            fragment: Some(bevy::render::render_resource::FragmentState {
+                shader: JFA_RESOLVE_SHADER_HANDLE,
+                shader_defs: Vec::new(),
+                entry_point: "resolve_jfa".into(),
+                targets: vec![Some(bevy::render::render_resource::ColorTargetState {
+                    format: TextureFormat::bevy_default(),
+                    blend: Some(bevy::render::render_resource::BlendState::ALPHA_BLENDING),
+                    write_mask: bevy::render::render_resource::ColorWrites::ALL,
+                })],
+            }),
+            primitive: Default::default(),
+            depth_stencil: None,
+            multisample: Default::default(),
+        });
+
+        Self {
+            jump_flood_layout,
+            jump_flood_pipeline,
+            resolve_layout,
+            resolve_pipeline,
+        }
+    }
+}
+
+/// Holds the textures the jump-flood mode needs for one view: the seed/mask
+/// texture written by [`queue_outline_jfa_seed`], and the two ping-pong
+/// textures the jump-flood passes read from and write to on alternating
+/// iterations. Sized to the view's physical resolution and recreated
+/// whenever that changes.
+#[derive(Component)]
+pub(crate) struct JfaTextures {
+    seed: TextureView,
+    ping_pong: [TextureView; 2],
+    size: Extent3d,
+    /// Widest `OutlineJfaSettings::width` requested by a visible entity this
+    /// frame; drives both the initial jump step and the resolve distance test.
+    width: f32,
+    /// Colour of the entity that `width` was taken from.
+    colour: [f32; 4],
+}
+
+impl JfaTextures {
+    pub(crate) fn new(
+        render_device: &RenderDevice,
+        texture_cache: &mut TextureCache,
+        size: Extent3d,
+        width: f32,
+        colour: [f32; 4],
+    ) -> Self {
+        let make = |label: &'static str| {
+            texture_cache
+                .get(
+                    render_device,
+                    bevy::render::render_resource::TextureDescriptor {
+                        label: Some(label),
+                        size,
+                        mip_level_count: 1,
+                        sample_count: 1,
+                        dimension: bevy::render::render_resource::TextureDimension::D2,
+                        // Two 16-bit channels store the seed's pixel coordinate;
+                        // a coordinate of (u16::MAX, u16::MAX) is the "no seed"
+                        // sentinel used before the first jump-flood pass runs.
+                        format: TextureFormat::Rg16Uint,
+                        usage: TextureUsages::TEXTURE_BINDING
+                            | TextureUsages::RENDER_ATTACHMENT
+                            | TextureUsages::STORAGE_BINDING,
+                        view_formats: &[],
+                    },
+                )
+                .default_view
+        };
+        Self {
+            seed: make("outline_jfa_seed_texture"),
+            ping_pong: [make("outline_jfa_ping_texture"), make("outline_jfa_pong_texture")],
+            size,
+            width,
+            colour,
+        }
+    }
+
+    pub(crate) fn seed_attachment(&self) -> &TextureView {
+        &self.seed
+    }
+
+    pub(crate) fn ping_pong(&self, index: usize) -> &TextureView {
+        &self.ping_pong[index % 2]
+    }
+
+    /// `ceil(log2(max(width, height)))` ping-pong passes (at least 1) are
+    /// enough for the jump-flood search to have covered the whole texture.
+    pub(crate) fn pass_count(&self) -> u32 {
+        jump_flood_pass_count(self.size)
+    }
+
+    /// Jump step used by the first ping-pong pass; halves every pass after.
+    pub(crate) fn initial_step(&self) -> u32 {
+        initial_jump_step(self.width)
+    }
+
+    /// Index of the ping-pong texture holding the final result: pass `i`
+    /// (0-indexed) writes to slot `i % 2`, so after `pass_count()` passes the
+    /// last one written is `pass_count() - 1`.
+    pub(crate) fn final_index(&self) -> usize {
+        (self.pass_count() as usize - 1) % 2
+    }
+
+    pub(crate) fn width(&self) -> f32 {
+        self.width
+    }
+
+    pub(crate) fn colour(&self) -> [f32; 4] {
+        self.colour
+    }
+
+    pub(crate) fn size(&self) -> Extent3d {
+        self.size
+    }
+}
+
+/// Creates or resizes each view's [`JfaTextures`] for this frame, sized to
+/// the widest jump-flood outline visible to that view. Views with no visible
+/// jump-flood entities this frame are left without the component, so
+/// [`JumpFloodNode`]/[`JfaResolveNode`] skip them entirely.
+///
+/// The jump-flood mode shades every seeded pixel in a view with a single
+/// [`OutlineJfaSettings::width`]/`colour` pair — whichever entity has the
+/// largest `width` — rather than per entity: the seed texture only stores a
+/// nearest-seed pixel coordinate, with no room for a per-entity colour or
+/// palette index. See [`OutlineJfaSettings`] for what this means for a scene
+/// mixing multiple jump-flood entities on the same view.
+pub(crate) fn prepare_jfa_textures(
+    mut commands: Commands,
+    render_device: Res<RenderDevice>,
+    mut texture_cache: ResMut<bevy::render::texture::TextureCache>,
+    material_meshes: Query<(&OutlineJfaSettings, &OutlineRenderLayers)>,
+    views: Query<(
+        Entity,
+        &bevy::render::camera::ExtractedCamera,
+        Option<&RenderLayers>,
+    )>,
+) {
+    for (view_entity, camera, view_mask) in views.iter() {
+        let Some(target_size) = camera.physical_target_size else {
+            continue;
+        };
+        let view_mask = view_mask.copied().unwrap_or_default();
+        let Some((settings, _)) = material_meshes
+            .iter()
+            .filter(|(_, mask)| view_mask.intersects(mask))
+            .max_by(|(a, _), (b, _)| a.width.total_cmp(&b.width))
+        else {
+            continue; // No jump-flood outlines visible on this view this frame
+        };
+        if settings.width <= 0.0 {
+            continue;
+        }
+        let size = Extent3d {
+            width: target_size.x,
+            height: target_size.y,
+            depth_or_array_layers: 1,
+        };
+        commands.entity(view_entity).insert(JfaTextures::new(
+            &render_device,
+            &mut texture_cache,
+            size,
+            settings.width,
+            settings.colour,
+        ));
+    }
+}
+
+/// Width of the jump flood outline, expressed in pixels.
+///
+/// Attach this alongside [`OutlineRenderLayers`] on any entity that should be
+/// outlined by the jump-flood pass instead of the volume-extrusion pass. The
+/// two modes can be mixed freely within a scene: an entity is only queued for
+/// jump-flood once it carries this component.
+///
+/// `width` and `colour` are per-view, not per-entity: when more than one
+/// jump-flood entity is visible to the same view, [`prepare_jfa_textures`]
+/// picks the widest entity's `width` and `colour` and shades every jump-flood
+/// outline on that view with them. Give every jump-flood entity on a shared
+/// view the same `width`/`colour` (or put them on separate
+/// [`OutlineRenderLayers`]) if they need to look different.
+#[derive(Component, Copy, Clone, Debug, PartialEq, Reflect)]
+#[reflect(Component, Default)]
+pub struct OutlineJfaSettings {
+    /// The outline width, in physical pixels.
+    pub width: f32,
+    /// The colour [`crate::node::JfaResolveNode`] shades the outline with.
+    /// One colour applies per view; see the struct docs.
+    pub colour: [f32; 4],
+}
+
+impl Default for OutlineJfaSettings {
+    fn default() -> Self {
+        Self {
+            width: 4.0,
+            colour: [1.0, 1.0, 1.0, 1.0],
+        }
+    }
+}
+
+pub(crate) type DrawJfaSeed = (
+    SetItemPipeline,
+    SetMeshViewBindGroup<0>,
+    SetMeshBindGroup<1>,
+    SetOutlineViewBindGroup<2>,
+    SetOutlineStencilBindGroup<3>,
+    DrawMesh,
+);
+
+/// Renders every mesh carrying [`OutlineJfaSettings`] into the seed texture.
+///
+/// Covered texels are initialised to their own pixel coordinate; texels left
+/// untouched by this pass keep the sentinel value the seed texture was
+/// cleared to, so the following jump-flood passes can distinguish "no seed
+/// nearby yet" from "this is a seed".
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub(crate) fn queue_outline_jfa_seed(
+    seed_draw_functions: Res<DrawFunctions<JfaSeedOutline>>,
+    jfa_pipeline: Res<OutlinePipeline>,
+    msaa: Res<Msaa>,
+    mut pipelines: ResMut<SpecializedMeshPipelines<OutlinePipeline>>,
+    pipeline_cache: Res<PipelineCache>,
+    render_meshes: Res<RenderAssets<Mesh>>,
+    adapter_info: Res<RenderAdapterInfo>,
+    material_meshes: Query<(
+        Entity,
+        &Handle<Mesh>,
+        &OutlineJfaSettings,
+        &OutlineRenderLayers,
+    )>,
+    mut views: Query<(
+        &ExtractedView,
+        &mut RenderPhase<JfaSeedOutline>,
+        Option<&RenderLayers>,
+    )>,
+) {
+    let draw_jfa_seed = seed_draw_functions.read().get_id::<DrawJfaSeed>().unwrap();
+
+    let base_key = PipelineKey::new()
+        .with_msaa(*msaa)
+        .with_pass_type(PassType::JfaSeed)
+        .with_opengl_workaround(adapter_info.0.backend == Backend::Gl);
+
+    for (_view, mut seed_phase, view_mask) in views.iter_mut() {
+        let view_mask = view_mask.copied().unwrap_or_default();
+        for (entity, mesh_handle, jfa_settings, outline_mask) in material_meshes.iter() {
+            if !view_mask.intersects(outline_mask) {
+                continue; // Layer not enabled
+            }
+            if jfa_settings.width <= 0.0 {
+                continue; // Zero-width outline, nothing to seed
+            }
+            let Some(mesh) = render_meshes.get(mesh_handle) else {
+                continue;
+            };
+            let key = base_key.with_primitive_topology(mesh.primitive_topology);
+            let Ok(pipeline) =
+                pipelines.specialize(&pipeline_cache, &jfa_pipeline, key, &mesh.layout)
+            else {
+                continue; // Pipeline still compiling; try again next frame
+            };
+            // The seed pass only ever writes a pixel's own coordinate, so
+            // unlike the stencil/volume phases it has no back-to-front
+            // ordering requirement: every seed item sorts the same.
+            seed_phase.add(JfaSeedOutline {
+                entity,
+                pipeline,
+                draw_function: draw_jfa_seed,
+                distance: 0.0,
+                // Like the stencil/volume phases, the seed pass isn't
+                // batched: each item draws exactly one mesh instance.
+                batch_range: 0..1,
+                dynamic_offset: None,
+            });
+        }
+    }
+}
@@ -0,0 +1,73 @@
+mod draw;
+mod jfa;
+mod node;
+mod pipeline;
+mod uniforms;
+mod view_uniforms;
+
+use bevy::core_pipeline::core_3d::graph::{Core3d, Node3d};
+use bevy::prelude::*;
+use bevy::render::render_graph::RenderGraphApp;
+use bevy::render::render_phase::{AddRenderCommand, DrawFunctions};
+use bevy::render::render_resource::SpecializedMeshPipelines;
+use bevy::render::view::RenderLayers as BevyRenderLayers;
+use bevy::render::{Render, RenderApp, RenderSet};
+
+use jfa::{JfaPipeline, JfaStepBuffer};
+use node::{JfaResolveNode, JumpFloodNode, JFA_RESOLVE_NODE, JUMP_FLOOD_NODE};
+
+pub use jfa::OutlineJfaSettings;
+
+/// Which of a scene's render layers an outlined entity is visible on.
+///
+/// This mirrors Bevy's own [`BevyRenderLayers`] rather than reusing it
+/// directly, so that toggling outline visibility never also changes what a
+/// camera renders for the entity's base mesh.
+#[derive(Component, Copy, Clone, Debug, Default, PartialEq, Eq, Deref, DerefMut, Reflect)]
+#[reflect(Component, Default)]
+pub struct OutlineRenderLayers(pub BevyRenderLayers);
+
+pub struct OutlinePlugin;
+
+impl Plugin for OutlinePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<OutlineRenderLayers>()
+            .register_type::<OutlineJfaSettings>();
+
+        jfa::build(app);
+
+        let Ok(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app
+            .init_resource::<SpecializedMeshPipelines<pipeline::OutlinePipeline>>()
+            .init_resource::<JfaPipeline>()
+            .init_resource::<JfaStepBuffer>()
+            .add_render_command::<node::StencilOutline, draw::DrawStencil>()
+            .add_render_command::<node::OpaqueOutline, draw::DrawOutline>()
+            .add_render_command::<node::TransparentOutline, draw::DrawOutline>()
+            .add_render_command::<node::JfaSeedOutline, jfa::DrawJfaSeed>()
+            .add_systems(
+                Render,
+                (
+                    draw::queue_outline_stencil_mesh,
+                    draw::queue_outline_volume_mesh,
+                    jfa::queue_outline_jfa_seed,
+                )
+                    .in_set(RenderSet::Queue),
+            )
+            .add_systems(
+                Render,
+                (jfa::prepare_jfa_textures, jfa::prepare_jfa_steps)
+                    .chain()
+                    .in_set(RenderSet::Prepare),
+            )
+            .add_render_graph_node::<node::JfaSeedNode>(Core3d, node::JFA_SEED_NODE)
+            .add_render_graph_node::<JumpFloodNode>(Core3d, JUMP_FLOOD_NODE)
+            .add_render_graph_node::<JfaResolveNode>(Core3d, JFA_RESOLVE_NODE)
+            .add_render_graph_edge(Core3d, node::JFA_SEED_NODE, JUMP_FLOOD_NODE)
+            .add_render_graph_edge(Core3d, JUMP_FLOOD_NODE, Node3d::MainOpaquePass)
+            .add_render_graph_edge(Core3d, Node3d::MainTransparentPass, JFA_RESOLVE_NODE);
+    }
+}
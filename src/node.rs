@@ -0,0 +1,304 @@
+use std::ops::Range;
+
+use bevy::ecs::entity::Entity;
+use bevy::ecs::query::QueryState;
+use bevy::ecs::world::{FromWorld, World};
+use bevy::math::Vec4;
+use bevy::render::camera::ExtractedCamera;
+use bevy::render::render_graph::{Node, NodeRunError, RenderGraphContext};
+use bevy::render::render_phase::{
+    CachedRenderPipelinePhaseItem, DrawFunctionId, PhaseItem, RenderPhase,
+};
+use bevy::render::render_resource::{
+    BindGroupEntries, CachedRenderPipelineId, ComputePassDescriptor, LoadOp, Operations,
+    PipelineCache, UniformBuffer,
+};
+use bevy::render::renderer::{RenderContext, RenderDevice, RenderQueue};
+use bevy::render::view::ViewTarget;
+
+use crate::jfa::{JfaPipeline, JfaResolveUniform, JfaStepBuffer, JfaStepOffsets, JfaTextures};
+
+macro_rules! outline_phase_item {
+    ($name:ident) => {
+        pub(crate) struct $name {
+            pub(crate) entity: Entity,
+            pub(crate) pipeline: CachedRenderPipelineId,
+            pub(crate) draw_function: DrawFunctionId,
+            pub(crate) distance: f32,
+            pub(crate) batch_range: Range<u32>,
+            pub(crate) dynamic_offset: Option<std::num::NonZeroU32>,
+        }
+
+        impl PhaseItem for $name {
+            type SortKey = bevy::utils::FloatOrd;
+
+            fn entity(&self) -> Entity {
+                self.entity
+            }
+
+            fn sort_key(&self) -> Self::SortKey {
+                bevy::utils::FloatOrd(self.distance)
+            }
+
+            fn draw_function(&self) -> DrawFunctionId {
+                self.draw_function
+            }
+
+            fn batch_range(&self) -> &Range<u32> {
+                &self.batch_range
+            }
+
+            fn batch_range_mut(&mut self) -> &mut Range<u32> {
+                &mut self.batch_range
+            }
+
+            fn dynamic_offset(&self) -> Option<std::num::NonZeroU32> {
+                self.dynamic_offset
+            }
+
+            fn dynamic_offset_mut(&mut self) -> &mut Option<std::num::NonZeroU32> {
+                &mut self.dynamic_offset
+            }
+        }
+
+        impl CachedRenderPipelinePhaseItem for $name {
+            fn cached_pipeline(&self) -> CachedRenderPipelineId {
+                self.pipeline
+            }
+        }
+    };
+}
+
+outline_phase_item!(StencilOutline);
+outline_phase_item!(OpaqueOutline);
+outline_phase_item!(TransparentOutline);
+/// Phase item for the jump-flood seed pass. See [`crate::jfa`].
+outline_phase_item!(JfaSeedOutline);
+
+/// Render graph node name for [`JfaSeedNode`], registered by `OutlinePlugin`
+/// and followed in the graph by the ping-pong jump-flood node.
+pub(crate) const JFA_SEED_NODE: &str = "outline_jfa_seed";
+
+/// Draws every [`JfaSeedOutline`] phase item into the jump-flood seed
+/// texture owned by [`crate::jfa::JfaTextures`].
+pub(crate) struct JfaSeedNode {
+    query: QueryState<(
+        &'static RenderPhase<JfaSeedOutline>,
+        &'static ExtractedCamera,
+        &'static JfaTextures,
+    )>,
+}
+
+impl FromWorld for JfaSeedNode {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            query: world.query(),
+        }
+    }
+}
+
+impl Node for JfaSeedNode {
+    fn update(&mut self, world: &mut World) {
+        self.query.update_archetypes(world);
+    }
+
+    fn run(
+        &self,
+        graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let view_entity = graph.view_entity();
+        // No match means either this view has no jump-flood phase items, or
+        // `prepare_jfa_textures` found nothing to seed this frame; either way
+        // there's nothing for this node to draw.
+        let Ok((seed_phase, camera, jfa_textures)) = self.query.get_manual(world, view_entity)
+        else {
+            return Ok(());
+        };
+        let attachment = jfa_textures.seed_attachment();
+        let mut render_pass = render_context.begin_tracked_render_pass(
+            bevy::render::render_resource::RenderPassDescriptor {
+                label: Some("outline_jfa_seed_pass"),
+                color_attachments: &[Some(bevy::render::render_resource::RenderPassColorAttachment {
+                    view: attachment,
+                    resolve_target: None,
+                    ops: Operations {
+                        // RG must clear to the shaders' `NO_SEED` sentinel
+                        // (0xffffu, 0xffffu), not (0, 0) — (0, 0) is a valid
+                        // texel coordinate and would flood from the top-left
+                        // corner instead of marking uncovered texels as unseeded.
+                        load: LoadOp::Clear(wgpu_types::Color {
+                            r: 65535.0,
+                            g: 65535.0,
+                            b: 0.0,
+                            a: 0.0,
+                        }),
+                        store: true,
+                    },
+                })],
+                depth_stencil_attachment: None,
+            },
+        );
+        if let Some(viewport) = &camera.viewport {
+            render_pass.set_camera_viewport(viewport);
+        }
+        seed_phase.render(&mut render_pass, world, view_entity);
+        Ok(())
+    }
+}
+
+/// Render graph node name for [`JumpFloodNode`].
+pub(crate) const JUMP_FLOOD_NODE: &str = "outline_jfa_jump_flood";
+/// Render graph node name for [`JfaResolveNode`].
+pub(crate) const JFA_RESOLVE_NODE: &str = "outline_jfa_resolve";
+
+/// Runs the ping-pong jump-flood compute passes: each pass reads the
+/// previous pass's output (or the seed texture, for the first pass) and
+/// writes the alternate ping-pong texture, narrowing in on each texel's
+/// nearest seed. See [`crate::jfa`] for the algorithm.
+pub(crate) struct JumpFloodNode {
+    query: QueryState<(&'static JfaTextures, &'static JfaStepOffsets)>,
+}
+
+impl FromWorld for JumpFloodNode {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            query: world.query(),
+        }
+    }
+}
+
+impl Node for JumpFloodNode {
+    fn update(&mut self, world: &mut World) {
+        self.query.update_archetypes(world);
+    }
+
+    fn run(
+        &self,
+        graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let view_entity = graph.view_entity();
+        let Ok((jfa_textures, step_offsets)) = self.query.get_manual(world, view_entity) else {
+            return Ok(());
+        };
+        let jfa_pipeline = world.resource::<JfaPipeline>();
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let Some(compute_pipeline) =
+            pipeline_cache.get_compute_pipeline(jfa_pipeline.jump_flood_pipeline)
+        else {
+            return Ok(()); // Still compiling; skip this frame rather than block.
+        };
+        let step_buffer = world.resource::<JfaStepBuffer>();
+        let Some(step_binding) = step_buffer.buffer.binding() else {
+            return Ok(());
+        };
+        let render_device = world.resource::<RenderDevice>();
+
+        let size = jfa_textures.size();
+        let workgroups_x = size.width.div_ceil(8);
+        let workgroups_y = size.height.div_ceil(8);
+
+        for (pass_index, &offset) in step_offsets.0.iter().enumerate() {
+            let input = if pass_index == 0 {
+                jfa_textures.seed_attachment()
+            } else {
+                jfa_textures.ping_pong(pass_index - 1)
+            };
+            let output = jfa_textures.ping_pong(pass_index);
+            let bind_group = render_device.create_bind_group(
+                Some("outline_jfa_jump_flood_bind_group"),
+                &jfa_pipeline.jump_flood_layout,
+                &BindGroupEntries::sequential((input, output, step_binding.clone())),
+            );
+            let mut compute_pass =
+                render_context
+                    .command_encoder()
+                    .begin_compute_pass(&ComputePassDescriptor {
+                        label: Some("outline_jfa_jump_flood_pass"),
+                        timestamp_writes: None,
+                    });
+            compute_pass.set_pipeline(compute_pipeline);
+            compute_pass.set_bind_group(0, &bind_group, &[offset]);
+            compute_pass.dispatch_workgroups(workgroups_x, workgroups_y, 1);
+        }
+        Ok(())
+    }
+}
+
+/// Final jump-flood pass: shades each texel whose nearest seed (found by
+/// [`JumpFloodNode`]) is within the outline width, compositing the result
+/// onto the view target. See [`crate::jfa`].
+pub(crate) struct JfaResolveNode {
+    query: QueryState<(&'static ViewTarget, &'static JfaTextures)>,
+}
+
+impl FromWorld for JfaResolveNode {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            query: world.query(),
+        }
+    }
+}
+
+impl Node for JfaResolveNode {
+    fn update(&mut self, world: &mut World) {
+        self.query.update_archetypes(world);
+    }
+
+    fn run(
+        &self,
+        graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let view_entity = graph.view_entity();
+        let Ok((target, jfa_textures)) = self.query.get_manual(world, view_entity) else {
+            return Ok(());
+        };
+        let jfa_pipeline = world.resource::<JfaPipeline>();
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let Some(render_pipeline) =
+            pipeline_cache.get_render_pipeline(jfa_pipeline.resolve_pipeline)
+        else {
+            return Ok(());
+        };
+        let render_device = world.resource::<RenderDevice>();
+        let render_queue = world.resource::<RenderQueue>();
+
+        let mut resolve_uniform = UniformBuffer::from(JfaResolveUniform {
+            colour: Vec4::from(jfa_textures.colour()),
+            width: jfa_textures.width(),
+        });
+        resolve_uniform.write_buffer(render_device, render_queue);
+        let Some(resolve_binding) = resolve_uniform.binding() else {
+            return Ok(());
+        };
+
+        let bind_group = render_device.create_bind_group(
+            Some("outline_jfa_resolve_bind_group"),
+            &jfa_pipeline.resolve_layout,
+            &BindGroupEntries::sequential((
+                jfa_textures.ping_pong(jfa_textures.final_index()),
+                resolve_binding,
+            )),
+        );
+
+        let mut render_pass = render_context.begin_tracked_render_pass(
+            bevy::render::render_resource::RenderPassDescriptor {
+                label: Some("outline_jfa_resolve_pass"),
+                color_attachments: &[Some(target.get_color_attachment(Operations {
+                    load: LoadOp::Load,
+                    store: true,
+                }))],
+                depth_stencil_attachment: None,
+            },
+        );
+        render_pass.set_render_pipeline(render_pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+        Ok(())
+    }
+}
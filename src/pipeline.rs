@@ -0,0 +1,142 @@
+use bevy::pbr::MeshPipeline;
+use bevy::prelude::*;
+use bevy::render::mesh::PrimitiveTopology;
+use bevy::render::render_resource::*;
+
+use crate::uniforms::DepthMode;
+
+/// Which render phase a specialized outline pipeline targets. In `specialize`
+/// the variant only picks the last bind group layout (stencil layout for
+/// `Stencil`/`JfaSeed`, volume layout for `Opaque`/`Transparent`); the
+/// fragment entry point and blend state are the same for every variant.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub(crate) enum PassType {
+    Stencil,
+    Opaque,
+    Transparent,
+    /// Writes the jump-flood seed texture. See [`crate::jfa`].
+    JfaSeed,
+}
+
+/// Flags describing a single specialized variant of [`OutlinePipeline`].
+///
+/// Each `with_*` builder flips a field and returns `self`, mirroring the
+/// bitflags-style specialization keys used elsewhere in `bevy`'s own
+/// rendering crates.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub(crate) struct PipelineKey {
+    msaa_samples: u32,
+    pass_type: PassType,
+    primitive_topology: PrimitiveTopology,
+    depth_mode: DepthMode,
+    offset_zero: bool,
+    hdr: bool,
+    opengl_workaround: bool,
+    occluded_style: bool,
+}
+
+impl PipelineKey {
+    pub(crate) fn new() -> Self {
+        Self {
+            msaa_samples: 1,
+            pass_type: PassType::Stencil,
+            primitive_topology: PrimitiveTopology::TriangleList,
+            depth_mode: DepthMode::Flat,
+            offset_zero: true,
+            hdr: false,
+            opengl_workaround: false,
+            occluded_style: false,
+        }
+    }
+
+    pub(crate) fn with_msaa(mut self, msaa: Msaa) -> Self {
+        self.msaa_samples = msaa.samples();
+        self
+    }
+
+    pub(crate) fn with_pass_type(mut self, pass_type: PassType) -> Self {
+        self.pass_type = pass_type;
+        self
+    }
+
+    pub(crate) fn with_primitive_topology(mut self, primitive_topology: PrimitiveTopology) -> Self {
+        self.primitive_topology = primitive_topology;
+        self
+    }
+
+    pub(crate) fn with_depth_mode(mut self, depth_mode: DepthMode) -> Self {
+        self.depth_mode = depth_mode;
+        self
+    }
+
+    pub(crate) fn with_offset_zero(mut self, offset_zero: bool) -> Self {
+        self.offset_zero = offset_zero;
+        self
+    }
+
+    pub(crate) fn with_hdr_format(mut self, hdr: bool) -> Self {
+        self.hdr = hdr;
+        self
+    }
+
+    pub(crate) fn with_opengl_workaround(mut self, opengl_workaround: bool) -> Self {
+        self.opengl_workaround = opengl_workaround;
+        self
+    }
+
+    /// Selects the occluded-style sub-pass: depth-test `Greater` instead of
+    /// the normal `LessEqual` visible-surface pass. This flag changes only the
+    /// depth comparison; `specialize` adds no shader def for it, so no tint
+    /// is selected by the pipeline. See
+    /// [`crate::draw::queue_outline_volume_mesh`].
+    pub(crate) fn with_occluded_style(mut self, occluded_style: bool) -> Self {
+        self.occluded_style = occluded_style;
+        self
+    }
+
+    pub(crate) fn depth_compare(&self) -> CompareFunction {
+        if self.occluded_style {
+            CompareFunction::Greater
+        } else {
+            CompareFunction::LessEqual
+        }
+    }
+}
+
+#[derive(Resource)]
+pub(crate) struct OutlinePipeline {
+    pub(crate) mesh_pipeline: MeshPipeline,
+    pub(crate) outline_view_layout: BindGroupLayout,
+    pub(crate) outline_stencil_layout: BindGroupLayout,
+    pub(crate) outline_volume_layout: BindGroupLayout,
+}
+
+impl SpecializedMeshPipeline for OutlinePipeline {
+    type Key = PipelineKey;
+
+    fn specialize(
+        &self,
+        key: Self::Key,
+        layout: &MeshVertexBufferLayoutRef,
+    ) -> Result<RenderPipelineDescriptor, SpecializedMeshPipelineError> {
+        let mut descriptor = self.mesh_pipeline.specialize(
+            bevy::pbr::MeshPipelineKey::from_primitive_topology(key.primitive_topology),
+            layout,
+        )?;
+        descriptor.multisample.count = key.msaa_samples;
+        descriptor.depth_stencil.as_mut().unwrap().depth_compare = key.depth_compare();
+        descriptor.layout = vec![
+            self.mesh_pipeline.view_layout.clone(),
+            self.mesh_pipeline.mesh_layouts.model_only.clone(),
+            self.outline_view_layout.clone(),
+            match key.pass_type {
+                PassType::Stencil | PassType::JfaSeed => self.outline_stencil_layout.clone(),
+                PassType::Opaque | PassType::Transparent => self.outline_volume_layout.clone(),
+            },
+        ];
+        if key.opengl_workaround {
+            descriptor.layout.truncate(3);
+        }
+        Ok(descriptor)
+    }
+}
@@ -0,0 +1,94 @@
+use bevy::ecs::system::lifetimeless::SRes;
+use bevy::ecs::system::SystemParamItem;
+use bevy::prelude::*;
+use bevy::render::render_phase::{PhaseItem, RenderCommand, RenderCommandResult, TrackedRenderPass};
+use bevy::render::render_resource::{BindGroup, ShaderType};
+
+/// How an outline's depth test behaves relative to the geometry it wraps.
+///
+/// `Invalid` is not a real rendering mode: it marks an entity whose outline
+/// components haven't finished propagating yet, and the stencil and volume
+/// queue systems in [`crate::draw`] skip it.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Reflect)]
+pub enum DepthMode {
+    #[default]
+    Invalid,
+    Flat,
+    Real,
+}
+
+#[derive(Component, Copy, Clone, Debug, ShaderType)]
+pub struct OutlineStencilUniform {
+    pub origin: Vec3,
+    pub offset: f32,
+}
+
+#[derive(Component, Copy, Clone, Debug)]
+pub struct OutlineStencilFlags {
+    pub depth_mode: DepthMode,
+}
+
+#[derive(Component, Copy, Clone, Debug, ShaderType)]
+pub struct OutlineVolumeUniform {
+    pub origin: Vec3,
+    pub offset: f32,
+}
+
+#[derive(Component, Copy, Clone, Debug)]
+pub struct OutlineVolumeFlags {
+    pub depth_mode: DepthMode,
+    /// Render a second, differently-tinted sub-pass for the portion of the
+    /// outline that's behind other geometry. See [`crate::draw`].
+    pub occluded_style: bool,
+}
+
+#[derive(Component, Copy, Clone, Debug, ShaderType)]
+pub struct OutlineFragmentUniform {
+    pub colour: [f32; 4],
+    /// Tint used for the occluded sub-pass when `OutlineVolumeFlags::occluded_style` is set.
+    pub occluded_colour: [f32; 4],
+}
+
+#[derive(Resource)]
+pub(crate) struct OutlineStencilBindGroup(pub(crate) BindGroup);
+
+pub(crate) struct SetOutlineStencilBindGroup<const I: usize>;
+
+impl<const I: usize, P: PhaseItem> RenderCommand<P> for SetOutlineStencilBindGroup<I> {
+    type Param = SRes<OutlineStencilBindGroup>;
+    type ViewQuery = ();
+    type ItemQuery = ();
+
+    fn render<'w>(
+        _item: &P,
+        _view: (),
+        _entity: Option<()>,
+        bind_group: SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        pass.set_bind_group(I, &bind_group.into_inner().0, &[]);
+        RenderCommandResult::Success
+    }
+}
+
+#[derive(Resource)]
+pub(crate) struct OutlineVolumeBindGroup(pub(crate) BindGroup);
+
+pub(crate) struct SetOutlineVolumeBindGroup<const I: usize>;
+
+impl<const I: usize, P: PhaseItem> RenderCommand<P> for SetOutlineVolumeBindGroup<I> {
+    type Param = SRes<OutlineVolumeBindGroup>;
+    type ViewQuery = ();
+    type ItemQuery = ();
+
+    fn render<'w>(
+        _item: &P,
+        _view: (),
+        _entity: Option<()>,
+        bind_group: SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        pass.set_bind_group(I, &bind_group.into_inner().0, &[]);
+        RenderCommandResult::Success
+    }
+}
@@ -0,0 +1,41 @@
+use bevy::ecs::system::lifetimeless::SRes;
+use bevy::ecs::system::SystemParamItem;
+use bevy::prelude::*;
+use bevy::render::render_phase::{PhaseItem, RenderCommand, RenderCommandResult, TrackedRenderPass};
+use bevy::render::render_resource::{BindGroup, BindGroupLayout, ShaderType};
+
+/// Per-view data shared by every outline draw: the view-projection matrix
+/// scaled into the units the outline shaders expect, plus the viewport size
+/// in pixels. The jump-flood seed draw in [`crate::jfa`] (`DrawJfaSeed`)
+/// binds this same group, so it lives here rather than in `jfa`; the
+/// jump-flood and resolve passes do not bind it.
+#[derive(Clone, ShaderType)]
+pub struct OutlineViewUniform {
+    pub view_proj: Mat4,
+    pub viewport_size: Vec2,
+}
+
+#[derive(Resource)]
+pub(crate) struct OutlineViewBindGroup {
+    pub(crate) bind_group: BindGroup,
+    pub(crate) layout: BindGroupLayout,
+}
+
+pub(crate) struct SetOutlineViewBindGroup<const I: usize>;
+
+impl<const I: usize, P: PhaseItem> RenderCommand<P> for SetOutlineViewBindGroup<I> {
+    type Param = SRes<OutlineViewBindGroup>;
+    type ViewQuery = ();
+    type ItemQuery = ();
+
+    fn render<'w>(
+        _item: &P,
+        _view: (),
+        _entity: Option<()>,
+        bind_group: SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        pass.set_bind_group(I, &bind_group.into_inner().bind_group, &[]);
+        RenderCommandResult::Success
+    }
+}